@@ -1,18 +1,35 @@
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use serde::{Deserialize, Serialize};
-use std::fs::{self, OpenOptions};
-use std::io::Write;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
 use std::path::PathBuf;
-use tauri::AppHandle;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
 
-/// Audit event types
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "snake_case")]
-pub enum AuditEventType {
-    CommandApproved,
-    CommandRejected,
-    CommandExecuted,
+/// `prev_hash` used by the very first record in the chain
+const GENESIS_PREV_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// In-memory state cached alongside the active log file
+struct ChainState {
+    /// Hash of the most recently appended record, so `write_audit_event`
+    /// doesn't need to re-read the whole file on every call. `None` when
+    /// the log is cleared or rotated, or before it has been read once this run.
+    tip: Option<String>,
+    /// Size in bytes of the active log file, updated on every append instead
+    /// of re-`stat`ing the file on every call. `None` when unknown (e.g. at
+    /// startup), forcing one real stat to seed it.
+    size: Option<u64>,
 }
 
+/// Guards `ChainState` and also serializes the whole read-tip/rotate/append/
+/// update sequence in `write_audit_event`, so concurrent command
+/// invocations can't fork the chain by reading the same tip before either
+/// has appended.
+static CHAIN_STATE: Mutex<ChainState> = Mutex::new(ChainState { tip: None, size: None });
+
 /// Audit event structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditEvent {
@@ -24,28 +41,294 @@ pub struct AuditEvent {
     #[serde(skip_serializing_if = "Option::is_none", rename = "exitCode")]
     pub exit_code: Option<i32>,
     pub timestamp: String,
+    /// Hash of the previous record in the chain (64 zeros for the genesis record)
+    #[serde(default)]
+    pub prev_hash: String,
+    /// `SHA256(canonical_json(event_without_hash) || prev_hash)`
+    #[serde(default)]
+    pub hash: String,
 }
 
 /// Get the path to the audit log file
 pub fn get_audit_log_path() -> Result<PathBuf, String> {
-    let home_dir = dirs::home_dir()
-        .ok_or_else(|| "Failed to get home directory".to_string())?;
+    let audit_log_path = crate::paths::audit_log_path()?;
+
+    if let Some(parent) = audit_log_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create Valet directory: {}", e))?;
+    }
+
+    Ok(audit_log_path)
+}
+
+/// Default size threshold that triggers audit log rotation, in bytes
+const DEFAULT_AUDIT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Default number of rotated archives to retain
+const DEFAULT_AUDIT_MAX_ARCHIVES: u32 = 10;
+
+/// Info about a single rotated, gzip-compressed audit archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditArchiveInfo {
+    pub filename: String,
+    pub size_bytes: u64,
+}
+
+/// Load audit log rotation settings from persisted settings
+fn load_audit_retention_settings() -> (u64, u32) {
+    if let Ok(settings) = crate::settings::load_settings() {
+        let max_bytes = crate::settings::get_u64(&settings, "audit_max_bytes", DEFAULT_AUDIT_MAX_BYTES);
+        let max_archives = settings
+            .get("audit_max_archives")
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_AUDIT_MAX_ARCHIVES);
+        (max_bytes, max_archives)
+    } else {
+        (DEFAULT_AUDIT_MAX_BYTES, DEFAULT_AUDIT_MAX_ARCHIVES)
+    }
+}
+
+/// List rotated audit archives, newest first (archive filenames embed a
+/// sortable timestamp, so lexicographic descending order is chronological)
+fn list_audit_archive_paths() -> Result<Vec<PathBuf>, String> {
+    let log_path = get_audit_log_path()?;
+    let dir = log_path
+        .parent()
+        .ok_or_else(|| "Audit log path has no parent directory".to_string())?;
+
+    let mut archives: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read audit directory: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("audit-") && name.ends_with(".log.gz"))
+        })
+        .collect();
+
+    archives.sort();
+    archives.reverse();
+
+    Ok(archives)
+}
+
+/// Compress the active audit log into a timestamped `.log.gz` archive and
+/// start a fresh active file, pruning archives beyond `max_archives`. Takes
+/// the already-locked chain state so the caller can hold one guard across
+/// rotation, append, and cache update; the file's size is read from `state`
+/// instead of re-`stat`ing it on every append, only falling back to a real
+/// stat when the cache is cold (e.g. right after startup).
+fn rotate_audit_log_if_needed(max_bytes: u64, max_archives: u32, state: &mut ChainState) -> Result<(), String> {
+    let log_path = get_audit_log_path()?;
+
+    let size = match state.size {
+        Some(size) => size,
+        None => match fs::metadata(&log_path) {
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        },
+    };
+
+    if size <= max_bytes {
+        state.size = Some(size);
+        return Ok(());
+    }
+
+    let dir = log_path
+        .parent()
+        .ok_or_else(|| "Audit log path has no parent directory".to_string())?;
+
+    let mut archive_path = dir.join(format!("audit-{}.log.gz", chrono::Utc::now().format("%Y%m%dT%H%M%SZ")));
+    let mut suffix = 1;
+    while archive_path.exists() {
+        archive_path = dir.join(format!(
+            "audit-{}-{}.log.gz",
+            chrono::Utc::now().format("%Y%m%dT%H%M%SZ"),
+            suffix
+        ));
+        suffix += 1;
+    }
+
+    let mut source = File::open(&log_path)
+        .map_err(|e| format!("Failed to open audit log for rotation: {}", e))?;
+    let archive_file = File::create(&archive_path)
+        .map_err(|e| format!("Failed to create audit archive: {}", e))?;
+    let mut encoder = GzEncoder::new(archive_file, Compression::default());
+
+    std::io::copy(&mut source, &mut encoder)
+        .map_err(|e| format!("Failed to compress audit log: {}", e))?;
+    encoder
+        .finish()
+        .map_err(|e| format!("Failed to finalize audit archive: {}", e))?;
+
+    fs::remove_file(&log_path)
+        .map_err(|e| format!("Failed to reset active audit log: {}", e))?;
+
+    // The active file now starts empty and a fresh genesis chain; archives
+    // keep their own internal chain but are no longer what new records
+    // link from or count toward the active file's size
+    state.tip = None;
+    state.size = Some(0);
+
+    // Prune archives beyond the retention limit, oldest first
+    let archives = list_audit_archive_paths()?;
+    for stale in archives.into_iter().skip(max_archives as usize) {
+        let _ = fs::remove_file(&stale);
+    }
+
+    Ok(())
+}
+
+/// Read and decompress a gzip-compressed audit archive
+fn read_gz_archive(path: &PathBuf) -> Result<String, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open audit archive {}: {}", path.display(), e))?;
+    let mut decoder = GzDecoder::new(file);
+    let mut contents = String::new();
+    decoder
+        .read_to_string(&mut contents)
+        .map_err(|e| format!("Failed to decompress audit archive {}: {}", path.display(), e))?;
+    Ok(contents)
+}
+
+/// Parse up to `limit` events from the tail of a JSON-lines string
+fn parse_tail_events(contents: &str, limit: usize) -> Vec<AuditEvent> {
+    let mut events: Vec<AuditEvent> = contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+
+    if events.len() > limit {
+        events = events.split_off(events.len() - limit);
+    }
+
+    events
+}
+
+/// Tauri command to update the audit log rotation thresholds
+#[tauri::command]
+pub fn configure_audit_retention(max_bytes: u64, max_archives: u32) -> Result<(), String> {
+    crate::settings::set_setting_command("audit_max_bytes".to_string(), max_bytes.to_string())?;
+    crate::settings::set_setting_command("audit_max_archives".to_string(), max_archives.to_string())?;
+    Ok(())
+}
+
+/// Tauri command to list rotated audit archives, newest first
+#[tauri::command]
+pub fn list_audit_archives() -> Result<Vec<AuditArchiveInfo>, String> {
+    list_audit_archive_paths()?
+        .into_iter()
+        .map(|path| {
+            let size_bytes = fs::metadata(&path)
+                .map_err(|e| format!("Failed to stat audit archive {}: {}", path.display(), e))?
+                .len();
+            let filename = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default()
+                .to_string();
+            Ok(AuditArchiveInfo { filename, size_bytes })
+        })
+        .collect()
+}
+
+/// Canonical JSON of an event's content fields, excluding `prev_hash`/`hash`,
+/// with a fixed key order so the hash is stable regardless of struct layout
+fn canonical_content_json(event: &AuditEvent) -> String {
+    let mut map = serde_json::Map::new();
+    map.insert("type".to_string(), serde_json::Value::String(event.event_type.clone()));
+    map.insert("command".to_string(), serde_json::Value::String(event.command.clone()));
+    if let Some(reason) = &event.reason {
+        map.insert("reason".to_string(), serde_json::Value::String(reason.clone()));
+    }
+    if let Some(exit_code) = event.exit_code {
+        map.insert("exitCode".to_string(), serde_json::Value::from(exit_code));
+    }
+    map.insert("timestamp".to_string(), serde_json::Value::String(event.timestamp.clone()));
+
+    serde_json::to_string(&serde_json::Value::Object(map)).unwrap_or_default()
+}
+
+/// Compute `SHA256(canonical_json(event_without_hash) || prev_hash)`
+fn compute_event_hash(event: &AuditEvent, prev_hash: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(canonical_content_json(event).as_bytes());
+    hasher.update(prev_hash.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Read the `hash` of the last well-formed record in the log, skipping
+/// blank lines and skipping back past a trailing corrupt/truncated line to
+/// the last good record before it. A log that is entirely corrupt (no good
+/// record found) is reported as an error rather than treated as empty, since
+/// collapsing either case to `None` would silently chain the next record off
+/// genesis and mask the very corruption the chain is meant to surface.
+fn read_last_hash_from_disk() -> Result<Option<String>, String> {
+    let log_path = get_audit_log_path()?;
+
+    if !log_path.exists() {
+        return Ok(None);
+    }
+
+    let contents = fs::read_to_string(&log_path)
+        .map_err(|e| format!("Failed to read audit log: {}", e))?;
+
+    let mut saw_corrupt_line = false;
 
-    let valet_dir = home_dir.join("Library/Application Support/Valet");
+    for line in contents.lines().rev() {
+        if line.trim().is_empty() {
+            continue;
+        }
 
-    // Ensure the Valet directory exists
-    fs::create_dir_all(&valet_dir)
-        .map_err(|e| format!("Failed to create Valet directory: {}", e))?;
+        match serde_json::from_str::<AuditEvent>(line) {
+            Ok(event) => return Ok(Some(event.hash)),
+            Err(_) => {
+                saw_corrupt_line = true;
+                continue;
+            }
+        }
+    }
 
-    Ok(valet_dir.join("audit.log"))
+    if saw_corrupt_line {
+        return Err("Audit log is corrupt: no well-formed record found to chain from".to_string());
+    }
+
+    Ok(None)
 }
 
-/// Write an audit event to the log file
-pub fn write_audit_event(event: &AuditEvent) -> Result<(), String> {
+/// Write an audit event to the log file, chaining it onto the previous
+/// record's hash, then broadcast it to every webview window so the
+/// frontend doesn't have to poll `get_audit_events`.
+///
+/// Holds `CHAIN_STATE` locked across rotation, tip lookup, file append, and
+/// cache update. Tauri dispatches command handlers concurrently, so without
+/// this the tip could be read by two calls before either appended, letting
+/// both chain off the same `prev_hash` and fork the log.
+pub fn write_audit_event(app: &AppHandle, event: &AuditEvent) -> Result<(), String> {
     let log_path = get_audit_log_path()?;
 
+    let mut state = CHAIN_STATE
+        .lock()
+        .map_err(|e| format!("Failed to lock audit chain state: {}", e))?;
+
+    let (max_bytes, max_archives) = load_audit_retention_settings();
+    rotate_audit_log_if_needed(max_bytes, max_archives, &mut state)?;
+
+    let prev_hash = match state.tip.clone() {
+        Some(tip) => tip,
+        None => read_last_hash_from_disk()?.unwrap_or_else(|| GENESIS_PREV_HASH.to_string()),
+    };
+
+    let hash = compute_event_hash(event, &prev_hash);
+
+    let mut chained_event = event.clone();
+    chained_event.prev_hash = prev_hash;
+    chained_event.hash = hash.clone();
+
     // Serialize event to JSON
-    let json = serde_json::to_string(event)
+    let json = serde_json::to_string(&chained_event)
         .map_err(|e| format!("Failed to serialize audit event: {}", e))?;
 
     // Open log file in append mode
@@ -59,57 +342,101 @@ pub fn write_audit_event(event: &AuditEvent) -> Result<(), String> {
     writeln!(file, "{}", json)
         .map_err(|e| format!("Failed to write to audit log: {}", e))?;
 
-    log::info!("Audit event logged: {}", event.event_type);
+    state.tip = Some(hash);
+    state.size = Some(state.size.unwrap_or(0) + json.len() as u64 + 1);
+    drop(state);
+
+    log::info!("Audit event logged: {}", chained_event.event_type);
+
+    let _ = app.emit("audit://event", &chained_event);
 
     Ok(())
 }
 
 /// Tauri command to log an audit event from the frontend
 #[tauri::command]
-pub fn log_audit_event(event: AuditEvent) -> Result<(), String> {
-    write_audit_event(&event)
+pub fn log_audit_event(app: AppHandle, event: AuditEvent) -> Result<(), String> {
+    write_audit_event(&app, &event)
 }
 
-/// Read recent audit events from the log file
+/// Read recent audit events from the log file, transparently reading back
+/// across rotated archives (newest first) when `limit` exceeds what the
+/// live file holds on its own
 /// Returns the last `limit` events (default: 100)
 pub fn read_audit_events(limit: Option<usize>) -> Result<Vec<AuditEvent>, String> {
+    let limit = limit.unwrap_or(100);
+    let log_path = get_audit_log_path()?;
+
+    let mut collected = if log_path.exists() {
+        let contents = fs::read_to_string(&log_path)
+            .map_err(|e| format!("Failed to read audit log: {}", e))?;
+        parse_tail_events(&contents, limit)
+    } else {
+        Vec::new()
+    };
+
+    for archive_path in list_audit_archive_paths()? {
+        if collected.len() >= limit {
+            break;
+        }
+
+        let remaining = limit - collected.len();
+        let contents = read_gz_archive(&archive_path)?;
+        let mut older = parse_tail_events(&contents, remaining);
+        older.extend(collected);
+        collected = older;
+    }
+
+    Ok(collected)
+}
+
+/// Tauri command to get recent audit events
+#[tauri::command]
+pub fn get_audit_events(limit: Option<usize>) -> Result<Vec<AuditEvent>, String> {
+    read_audit_events(limit)
+}
+
+/// Walk the audit log from genesis, recomputing each record's hash and
+/// confirming it chains from the previous one. Returns the index of the
+/// first broken or corrupt record, or `None` if the whole chain verifies.
+/// Only checks the active file; rotated archives, each of which holds its
+/// own chain starting from genesis, are not verified.
+#[tauri::command]
+pub fn verify_audit_log() -> Result<Option<usize>, String> {
     let log_path = get_audit_log_path()?;
 
-    // If the log file doesn't exist, return empty vector
     if !log_path.exists() {
-        return Ok(Vec::new());
+        return Ok(None);
     }
 
-    // Read the log file
     let contents = fs::read_to_string(&log_path)
         .map_err(|e| format!("Failed to read audit log: {}", e))?;
 
-    // Parse each line as a JSON event
-    let mut events = Vec::new();
-    for line in contents.lines() {
+    let mut expected_prev_hash = GENESIS_PREV_HASH.to_string();
+
+    for (index, line) in contents.lines().enumerate() {
         if line.trim().is_empty() {
             continue;
         }
 
-        match serde_json::from_str::<AuditEvent>(line) {
-            Ok(event) => events.push(event),
-            Err(e) => {
-                log::warn!("Failed to parse audit log line: {}", e);
-                continue;
-            }
+        let event: AuditEvent = match serde_json::from_str(line) {
+            Ok(event) => event,
+            // A corrupt or truncated line breaks the chain at this index
+            Err(_) => return Ok(Some(index)),
+        };
+
+        if event.prev_hash != expected_prev_hash {
+            return Ok(Some(index));
         }
-    }
 
-    // Return the last `limit` events
-    let limit = limit.unwrap_or(100);
-    let start = events.len().saturating_sub(limit);
-    Ok(events[start..].to_vec())
-}
+        if event.hash != compute_event_hash(&event, &expected_prev_hash) {
+            return Ok(Some(index));
+        }
 
-/// Tauri command to get recent audit events
-#[tauri::command]
-pub fn get_audit_events(limit: Option<usize>) -> Result<Vec<AuditEvent>, String> {
-    read_audit_events(limit)
+        expected_prev_hash = event.hash;
+    }
+
+    Ok(None)
 }
 
 /// Clear the audit log file
@@ -121,13 +448,36 @@ pub fn clear_audit_log() -> Result<(), String> {
             .map_err(|e| format!("Failed to clear audit log: {}", e))?;
     }
 
+    let mut state = CHAIN_STATE.lock().unwrap();
+    state.tip = None;
+    state.size = Some(0);
+
     Ok(())
 }
 
 /// Tauri command to clear the audit log
+///
+/// `authorize` records the authorization before the clear runs, but that
+/// record is erased along with everything else by the clear itself. Record
+/// it again once the log is empty, so the destructive action remains
+/// attributable instead of erasing the evidence of its own authorization.
 #[tauri::command]
-pub fn clear_audit_log_command() -> Result<(), String> {
-    clear_audit_log()
+pub fn clear_audit_log_command(app: AppHandle) -> Result<(), String> {
+    crate::capabilities::authorize(&app, "clear_audit_log_command", crate::capabilities::AuthContext::None)?;
+
+    clear_audit_log()?;
+
+    if let Some(capability) = crate::capabilities::load_capabilities(&app).commands.get("clear_audit_log_command") {
+        crate::capabilities::record_decision(
+            &app,
+            "clear_audit_log_command",
+            &capability.permission,
+            true,
+            Some("audit log cleared".to_string()),
+        );
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -142,6 +492,8 @@ mod tests {
             reason: None,
             exit_code: None,
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            prev_hash: GENESIS_PREV_HASH.to_string(),
+            hash: String::new(),
         };
 
         let json = serde_json::to_string(&event).unwrap();
@@ -159,9 +511,31 @@ mod tests {
             reason: None,
             exit_code: Some(0),
             timestamp: "2024-01-01T00:00:00Z".to_string(),
+            prev_hash: GENESIS_PREV_HASH.to_string(),
+            hash: String::new(),
         };
 
         let json = serde_json::to_string(&event).unwrap();
         assert!(json.contains("exitCode"));
     }
+
+    #[test]
+    fn test_compute_event_hash_is_deterministic() {
+        let event = AuditEvent {
+            event_type: "command_executed".to_string(),
+            command: "mo clean".to_string(),
+            reason: None,
+            exit_code: Some(0),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            prev_hash: String::new(),
+            hash: String::new(),
+        };
+
+        let hash_a = compute_event_hash(&event, GENESIS_PREV_HASH);
+        let hash_b = compute_event_hash(&event, GENESIS_PREV_HASH);
+        assert_eq!(hash_a, hash_b);
+
+        let hash_different_prev = compute_event_hash(&event, "different-prev-hash");
+        assert_ne!(hash_a, hash_different_prev);
+    }
 }
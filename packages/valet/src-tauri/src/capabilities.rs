@@ -0,0 +1,225 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+/// Optional narrowing applied on top of a command's required permission,
+/// e.g. restricting which key names or `mo` subcommands are allowed
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CommandScope {
+    #[serde(default)]
+    pub allowed_keys: Option<Vec<String>>,
+    #[serde(default)]
+    pub allowed_mole_subcommands: Option<Vec<String>>,
+}
+
+/// What it takes to run a single gated command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandCapability {
+    pub permission: String,
+    #[serde(default)]
+    pub scope: Option<CommandScope>,
+}
+
+/// Maps command names to the capability required to invoke them. Commands
+/// absent from the manifest are ungated.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CapabilitiesManifest {
+    pub commands: HashMap<String, CommandCapability>,
+}
+
+static MANIFEST: OnceLock<CapabilitiesManifest> = OnceLock::new();
+
+/// Built-in manifest used when no `capabilities.json` is bundled
+fn default_manifest() -> CapabilitiesManifest {
+    let mut commands = HashMap::new();
+    commands.insert(
+        "run_privileged_optimize".to_string(),
+        CommandCapability {
+            permission: "mole.privileged_optimize".to_string(),
+            scope: Some(CommandScope {
+                allowed_keys: None,
+                allowed_mole_subcommands: Some(vec!["optimize".to_string(), "deep-clean".to_string()]),
+            }),
+        },
+    );
+    commands.insert(
+        "store_key_command".to_string(),
+        CommandCapability {
+            permission: "keychain.write".to_string(),
+            scope: None,
+        },
+    );
+    commands.insert(
+        "delete_key_command".to_string(),
+        CommandCapability {
+            permission: "keychain.write".to_string(),
+            scope: None,
+        },
+    );
+    commands.insert(
+        "clear_audit_log_command".to_string(),
+        CommandCapability {
+            permission: "audit.clear".to_string(),
+            scope: None,
+        },
+    );
+    CapabilitiesManifest { commands }
+}
+
+/// Load (and cache) the capabilities manifest, preferring a bundled
+/// `capabilities.json` resource and falling back to the built-in defaults
+pub fn load_capabilities(app: &AppHandle) -> &'static CapabilitiesManifest {
+    MANIFEST.get_or_init(|| {
+        app.path()
+            .resource_dir()
+            .ok()
+            .map(|dir| dir.join("capabilities.json"))
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_else(default_manifest)
+    })
+}
+
+/// Context used to evaluate a command's scope, if it has one
+pub enum AuthContext<'a> {
+    None,
+    KeyName(&'a str),
+    MoleSubcommand(&'a str),
+}
+
+/// Raised when a command is denied by the capability layer
+#[derive(Debug, Clone)]
+pub struct CapabilityError {
+    pub reason: String,
+}
+
+impl std::fmt::Display for CapabilityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.reason)
+    }
+}
+
+impl From<CapabilityError> for String {
+    fn from(e: CapabilityError) -> String {
+        e.reason
+    }
+}
+
+/// Every permission referenced by the manifest, used to seed a fresh
+/// install's `granted_permissions` setting
+fn default_granted_permissions(manifest: &CapabilitiesManifest) -> HashSet<String> {
+    manifest.commands.values().map(|c| c.permission.clone()).collect()
+}
+
+/// Permissions granted to this install, read from settings. On a fresh
+/// install (no `granted_permissions` key persisted yet) every permission in
+/// the capabilities manifest is granted by default and persisted, so gated
+/// commands like storing a key or clearing the audit log work out of the
+/// box instead of being denied until a grant flow exists. Once the setting
+/// has been written at least once, it alone decides what's granted.
+fn granted_permissions(app: &AppHandle) -> HashSet<String> {
+    let settings = crate::settings::load_settings().unwrap_or_default();
+
+    if let Some(value) = settings.get("granted_permissions") {
+        return value
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+    }
+
+    let defaults = default_granted_permissions(load_capabilities(app));
+
+    let joined = defaults.iter().cloned().collect::<Vec<_>>().join(",");
+    if let Err(e) = crate::settings::set_setting_command("granted_permissions".to_string(), joined) {
+        log::warn!("Failed to persist seeded granted_permissions: {}", e);
+    }
+
+    defaults
+}
+
+/// Record whether a command was authorized or denied. Exposed crate-wide so
+/// a destructive command (e.g. clearing the audit log) can re-record its own
+/// authorization *after* the destructive action completes, when the normal
+/// pre-action record would otherwise be wiped out by the action itself.
+pub(crate) fn record_decision(app: &AppHandle, command: &str, permission: &str, authorized: bool, reason: Option<String>) {
+    let event = crate::audit::AuditEvent {
+        event_type: if authorized {
+            "command_authorized"
+        } else {
+            "command_denied"
+        }
+        .to_string(),
+        command: format!("{} (permission: {})", command, permission),
+        reason,
+        exit_code: None,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        prev_hash: String::new(),
+        hash: String::new(),
+    };
+
+    if let Err(e) = crate::audit::write_audit_event(app, &event) {
+        log::warn!("Failed to write capability audit event: {}", e);
+    }
+}
+
+/// Check whether `command` is authorized to run given `context`, recording
+/// an audit event either way. Commands with no manifest entry are ungated.
+pub fn authorize(app: &AppHandle, command: &str, context: AuthContext) -> Result<(), CapabilityError> {
+    let manifest = load_capabilities(app);
+
+    let Some(capability) = manifest.commands.get(command) else {
+        return Ok(());
+    };
+
+    let granted = granted_permissions(app);
+    if !granted.contains(&capability.permission) {
+        let reason = format!("missing permission '{}'", capability.permission);
+        record_decision(app, command, &capability.permission, false, Some(reason.clone()));
+        return Err(CapabilityError {
+            reason: format!("command '{}' denied: {}", command, reason),
+        });
+    }
+
+    if let Some(scope) = &capability.scope {
+        let scope_violation = match context {
+            AuthContext::KeyName(key_name) => scope
+                .allowed_keys
+                .as_ref()
+                .is_some_and(|allowed| !allowed.iter().any(|k| k == key_name)),
+            AuthContext::MoleSubcommand(subcommand) => scope
+                .allowed_mole_subcommands
+                .as_ref()
+                .is_some_and(|allowed| !allowed.iter().any(|s| s == subcommand)),
+            AuthContext::None => false,
+        };
+
+        if scope_violation {
+            let reason = "requested argument is outside the command's allowed scope".to_string();
+            record_decision(app, command, &capability.permission, false, Some(reason.clone()));
+            return Err(CapabilityError {
+                reason: format!("command '{}' denied: {}", command, reason),
+            });
+        }
+    }
+
+    record_decision(app, command, &capability.permission, true, None);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_granted_permissions_covers_every_manifest_command() {
+        let manifest = default_manifest();
+        let granted = default_granted_permissions(&manifest);
+
+        for capability in manifest.commands.values() {
+            assert!(granted.contains(&capability.permission));
+        }
+    }
+}
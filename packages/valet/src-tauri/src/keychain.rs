@@ -1,54 +1,103 @@
 use keyring::Entry;
+use tauri::AppHandle;
 
 const SERVICE_NAME: &str = "com.valet.mac";
 
-/// Store a key in the macOS Keychain
-#[tauri::command]
-pub fn store_key_command(key_name: String, key_value: String) -> Result<(), String> {
-    let entry = Entry::new(SERVICE_NAME, &key_name)
-        .map_err(|e| format!("Failed to create keychain entry: {}", e))?;
+/// A backend capable of storing small secrets (API keys, tokens) by name.
+///
+/// Implementations are expected to delegate to the platform credential
+/// store: the macOS Keychain, `secret-service` on Linux, and Windows
+/// Credential Manager.
+trait SecretStore {
+    fn store(&self, key_name: &str, key_value: &str) -> Result<(), String>;
+    fn get(&self, key_name: &str) -> Result<Option<String>, String>;
+    fn delete(&self, key_name: &str) -> Result<(), String>;
+    fn has(&self, key_name: &str) -> Result<bool, String>;
+}
 
-    entry.set_password(&key_value)
-        .map_err(|e| format!("Failed to store key in keychain: {}", e))?;
+/// `SecretStore` backed by the `keyring` crate, which itself dispatches to
+/// the macOS Keychain, Linux `secret-service`, or Windows Credential Manager
+/// depending on the target platform.
+struct KeyringSecretStore {
+    service_name: &'static str,
+}
 
-    Ok(())
+impl KeyringSecretStore {
+    fn entry(&self, key_name: &str) -> Result<Entry, String> {
+        Entry::new(self.service_name, key_name)
+            .map_err(|e| format!("Failed to create keychain entry: {}", e))
+    }
 }
 
-/// Retrieve a key from the macOS Keychain
-#[tauri::command]
-pub fn get_key_command(key_name: String) -> Result<Option<String>, String> {
-    let entry = Entry::new(SERVICE_NAME, &key_name)
-        .map_err(|e| format!("Failed to create keychain entry: {}", e))?;
+impl SecretStore for KeyringSecretStore {
+    fn store(&self, key_name: &str, key_value: &str) -> Result<(), String> {
+        self.entry(key_name)?
+            .set_password(key_value)
+            .map_err(|e| format!("Failed to store key in keychain: {}", e))
+    }
+
+    fn get(&self, key_name: &str) -> Result<Option<String>, String> {
+        match self.entry(key_name)?.get_password() {
+            Ok(password) => Ok(Some(password)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Failed to retrieve key from keychain: {}", e)),
+        }
+    }
 
-    match entry.get_password() {
-        Ok(password) => Ok(Some(password)),
-        Err(keyring::Error::NoEntry) => Ok(None),
-        Err(e) => Err(format!("Failed to retrieve key from keychain: {}", e)),
+    fn delete(&self, key_name: &str) -> Result<(), String> {
+        match self.entry(key_name)?.delete_credential() {
+            Ok(()) => Ok(()),
+            Err(keyring::Error::NoEntry) => Ok(()), // Already deleted or never existed
+            Err(e) => Err(format!("Failed to delete key from keychain: {}", e)),
+        }
+    }
+
+    fn has(&self, key_name: &str) -> Result<bool, String> {
+        match self.entry(key_name)?.get_password() {
+            Ok(_) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(e) => Err(format!("Failed to check key in keychain: {}", e)),
+        }
     }
 }
 
-/// Delete a key from the macOS Keychain
-#[tauri::command]
-pub fn delete_key_command(key_name: String) -> Result<(), String> {
-    let entry = Entry::new(SERVICE_NAME, &key_name)
-        .map_err(|e| format!("Failed to create keychain entry: {}", e))?;
-
-    match entry.delete_credential() {
-        Ok(()) => Ok(()),
-        Err(keyring::Error::NoEntry) => Ok(()), // Already deleted or never existed
-        Err(e) => Err(format!("Failed to delete key from keychain: {}", e)),
+/// The `SecretStore` used by the commands below
+fn secret_store() -> KeyringSecretStore {
+    KeyringSecretStore {
+        service_name: SERVICE_NAME,
     }
 }
 
-/// Check if a key exists in the macOS Keychain
+/// Store a key in the platform credential store
 #[tauri::command]
-pub fn has_key_command(key_name: String) -> Result<bool, String> {
-    let entry = Entry::new(SERVICE_NAME, &key_name)
-        .map_err(|e| format!("Failed to create keychain entry: {}", e))?;
+pub fn store_key_command(app: AppHandle, key_name: String, key_value: String) -> Result<(), String> {
+    crate::capabilities::authorize(
+        &app,
+        "store_key_command",
+        crate::capabilities::AuthContext::KeyName(&key_name),
+    )?;
+    secret_store().store(&key_name, &key_value)
+}
 
-    match entry.get_password() {
-        Ok(_) => Ok(true),
-        Err(keyring::Error::NoEntry) => Ok(false),
-        Err(e) => Err(format!("Failed to check key in keychain: {}", e)),
-    }
+/// Retrieve a key from the platform credential store
+#[tauri::command]
+pub fn get_key_command(key_name: String) -> Result<Option<String>, String> {
+    secret_store().get(&key_name)
+}
+
+/// Delete a key from the platform credential store
+#[tauri::command]
+pub fn delete_key_command(app: AppHandle, key_name: String) -> Result<(), String> {
+    crate::capabilities::authorize(
+        &app,
+        "delete_key_command",
+        crate::capabilities::AuthContext::KeyName(&key_name),
+    )?;
+    secret_store().delete(&key_name)
+}
+
+/// Check if a key exists in the platform credential store
+#[tauri::command]
+pub fn has_key_command(key_name: String) -> Result<bool, String> {
+    secret_store().has(&key_name)
 }
@@ -1,11 +1,14 @@
 use tauri::Manager;
 
 mod audit;
+mod capabilities;
 mod keychain;
 mod mole;
 mod monitoring;
+mod paths;
 mod permissions;
 mod settings;
+mod shortcut;
 mod workspace;
 
 /// Enable or disable auto-launch on system startup
@@ -98,7 +101,15 @@ pub fn run() {
       Some(vec!["--minimized"]), // Launch minimized (to menubar only)
     ))
     .plugin(tauri_plugin_shell::init())
-    .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+    .plugin(
+      tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, _shortcut, event| {
+          if event.state() == tauri_plugin_global_shortcut::ShortcutState::Pressed {
+            shortcut::summon_and_refresh(app);
+          }
+        })
+        .build(),
+    )
     .invoke_handler(tauri::generate_handler![
       set_autostart,
       is_autostart_enabled,
@@ -112,9 +123,13 @@ pub fn run() {
       audit::log_audit_event,
       audit::get_audit_events,
       audit::clear_audit_log_command,
+      audit::verify_audit_log,
+      audit::configure_audit_retention,
+      audit::list_audit_archives,
       monitoring::get_cached_status,
       monitoring::update_monitoring_config,
       monitoring::trigger_status_check,
+      monitoring::get_status_history,
       permissions::check_permissions_command,
       permissions::request_microphone_permission_command,
       permissions::open_system_preferences_command,
@@ -126,6 +141,7 @@ pub fn run() {
       keychain::get_key_command,
       keychain::delete_key_command,
       keychain::has_key_command,
+      shortcut::set_global_shortcut_command,
     ])
     .setup(|app| {
       if cfg!(debug_assertions) {
@@ -150,6 +166,11 @@ pub fn run() {
         log::info!("Workspace set up successfully");
       }
 
+      // Register the global shortcut that summons the menubar window
+      if let Err(e) = shortcut::register_configured_shortcut(app.handle()) {
+        log::warn!("Failed to register global shortcut: {}", e);
+      }
+
       // Start background monitoring
       monitoring::start_monitoring(app.handle().clone());
 
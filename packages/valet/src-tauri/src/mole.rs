@@ -1,7 +1,27 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager};
 
+/// Name of the manifest file written into the install directory
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Per-file record in the install manifest
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct ManifestEntry {
+    size: u64,
+    sha256: String,
+}
+
+/// Version/checksum manifest describing an installed Mole tree
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct InstallManifest {
+    version: String,
+    files: HashMap<String, ManifestEntry>,
+}
+
 /// Get the path to the bundled Mole resources directory
 fn get_bundled_mole_path(app: &AppHandle) -> Result<PathBuf, String> {
     let resource_path = app
@@ -12,15 +32,182 @@ fn get_bundled_mole_path(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(resource_path.join("mole"))
 }
 
-/// Get the installation directory for Mole in Application Support
+/// Get the installation directory for Mole in the app data directory
 fn get_mole_install_dir() -> Result<PathBuf, String> {
-    let home_dir = dirs::home_dir()
-        .ok_or_else(|| "Failed to get home directory".to_string())?;
+    crate::paths::bin_dir()
+}
+
+/// Read the bundled Mole version from a `VERSION` file or `package.json`
+fn read_bundled_version(bundled_mole_dir: &Path) -> String {
+    let version_file = bundled_mole_dir.join("VERSION");
+    if let Ok(contents) = fs::read_to_string(&version_file) {
+        let version = contents.trim();
+        if !version.is_empty() {
+            return version.to_string();
+        }
+    }
+
+    let package_json = bundled_mole_dir.join("package.json");
+    if let Ok(contents) = fs::read_to_string(&package_json) {
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(&contents) {
+            if let Some(version) = value.get("version").and_then(|v| v.as_str()) {
+                return version.to_string();
+            }
+        }
+    }
+
+    "unknown".to_string()
+}
+
+/// Compute the SHA-256 digest of a file as a lowercase hex string
+fn sha256_of_file(path: &Path) -> Result<String, String> {
+    let bytes = fs::read(path)
+        .map_err(|e| format!("Failed to read {} for hashing: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes);
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Walk a directory tree and build a manifest entry for every file, keyed by
+/// its path relative to `root`
+fn scan_tree(root: &Path, dir: &Path, files: &mut HashMap<String, ManifestEntry>) -> Result<(), String> {
+    for entry in fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?
+    {
+        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+        let path = entry.path();
+
+        if path.is_dir() {
+            scan_tree(root, &path, files)?;
+        } else {
+            let metadata = fs::metadata(&path)
+                .map_err(|e| format!("Failed to get metadata for {}: {}", path.display(), e))?;
+            let rel_path = path
+                .strip_prefix(root)
+                .map_err(|e| format!("Failed to compute relative path: {}", e))?
+                .to_string_lossy()
+                .replace('\\', "/");
+
+            files.insert(
+                rel_path,
+                ManifestEntry {
+                    size: metadata.len(),
+                    sha256: sha256_of_file(&path)?,
+                },
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Build a fresh manifest by scanning `mole_dir`
+fn build_manifest(version: &str, mole_dir: &Path) -> Result<InstallManifest, String> {
+    let mut files = HashMap::new();
+    scan_tree(mole_dir, mole_dir, &mut files)?;
+    Ok(InstallManifest {
+        version: version.to_string(),
+        files,
+    })
+}
+
+/// Load the manifest from an install directory, if present and well-formed.
+/// A missing or corrupt manifest is treated as "no manifest" so the caller
+/// falls back to a full reinstall.
+fn load_manifest(install_mole_dir: &Path) -> Option<InstallManifest> {
+    let manifest_path = install_mole_dir.join(MANIFEST_FILE_NAME);
+    let contents = fs::read_to_string(&manifest_path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Write the manifest into the install directory
+fn write_manifest(install_mole_dir: &Path, manifest: &InstallManifest) -> Result<(), String> {
+    let manifest_path = install_mole_dir.join(MANIFEST_FILE_NAME);
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    fs::write(&manifest_path, json)
+        .map_err(|e| format!("Failed to write manifest: {}", e))
+}
+
+/// Set executable permissions on a file (no-op on non-Unix)
+fn set_executable(path: &Path) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(path)
+            .map_err(|e| format!("Failed to get metadata for {}: {}", path.display(), e))?
+            .permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(path, perms)
+            .map_err(|e| format!("Failed to set permissions on {}: {}", path.display(), e))?;
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+    }
+
+    Ok(())
+}
+
+/// Copy files that changed (or are new) between the bundled tree and the
+/// installed manifest, and remove files that are no longer bundled
+fn sync_tree(
+    bundled_dir: &Path,
+    install_dir: &Path,
+    bundled_manifest: &InstallManifest,
+    installed_manifest: Option<&InstallManifest>,
+) -> Result<(), String> {
+    let empty = HashMap::new();
+    let installed_files = installed_manifest.map(|m| &m.files).unwrap_or(&empty);
+
+    for (rel_path, entry) in &bundled_manifest.files {
+        let needs_copy = match installed_files.get(rel_path) {
+            Some(installed_entry) => installed_entry != entry,
+            None => true,
+        };
+
+        if !needs_copy {
+            continue;
+        }
+
+        let src = bundled_dir.join(rel_path);
+        let dst = install_dir.join(rel_path);
+
+        if let Some(parent) = dst.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory {}: {}", parent.display(), e))?;
+        }
+
+        fs::copy(&src, &dst)
+            .map_err(|e| format!("Failed to copy file {} to {}: {}", src.display(), dst.display(), e))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let src_perms = fs::metadata(&src)
+                .map_err(|e| format!("Failed to get metadata: {}", e))?
+                .permissions();
+            fs::set_permissions(&dst, src_perms)
+                .map_err(|e| format!("Failed to set permissions: {}", e))?;
+        }
+    }
 
-    Ok(home_dir.join("Library/Application Support/Valet/bin"))
+    // Remove files that used to be bundled but no longer are, so stale
+    // scripts don't linger in the install directory.
+    for rel_path in installed_files.keys() {
+        if !bundled_manifest.files.contains_key(rel_path) {
+            let stale = install_dir.join(rel_path);
+            if stale.exists() {
+                let _ = fs::remove_file(&stale);
+            }
+        }
+    }
+
+    Ok(())
 }
 
-/// Ensure the Mole binary is installed and executable
+/// Ensure the Mole binary is installed and up to date
 pub fn ensure_mole_installed(app: &AppHandle) -> Result<PathBuf, String> {
     let bundled_mole_dir = get_bundled_mole_path(app)?;
     let install_dir = get_mole_install_dir()?;
@@ -30,109 +217,65 @@ pub fn ensure_mole_installed(app: &AppHandle) -> Result<PathBuf, String> {
     fs::create_dir_all(&install_dir)
         .map_err(|e| format!("Failed to create installation directory: {}", e))?;
 
-    // Check if mo is already installed and up to date
-    if install_path.exists() {
-        // TODO: Add version checking here in the future
-        // For now, we'll just return the existing installation
-        return Ok(install_path);
-    }
+    let install_mole_dir = crate::paths::mole_dir()?;
 
-    // Copy the entire bundled mole directory to the installation location
-    let install_mole_dir = install_dir.parent()
-        .ok_or_else(|| "Failed to get parent directory".to_string())?
-        .join("mole");
+    let bundled_version = read_bundled_version(&bundled_mole_dir);
+    let installed_manifest = load_manifest(&install_mole_dir);
 
-    // Remove existing installation if present
-    if install_mole_dir.exists() {
-        fs::remove_dir_all(&install_mole_dir)
-            .map_err(|e| format!("Failed to remove existing Mole directory: {}", e))?;
+    // If the installed version matches the bundled version and the manifest
+    // is well-formed, there's nothing to do.
+    if let Some(ref manifest) = installed_manifest {
+        if manifest.version == bundled_version && install_path.exists() {
+            return Ok(install_path);
+        }
     }
 
-    // Copy the bundled mole directory
-    copy_dir_recursive(&bundled_mole_dir, &install_mole_dir)?;
+    let bundled_manifest = build_manifest(&bundled_version, &bundled_mole_dir)?;
 
-    // Create a symlink from bin/mo to mole/mo
-    let mole_binary = install_mole_dir.join("mo");
+    fs::create_dir_all(&install_mole_dir)
+        .map_err(|e| format!("Failed to create directory {}: {}", install_mole_dir.display(), e))?;
 
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
+    sync_tree(
+        &bundled_mole_dir,
+        &install_mole_dir,
+        &bundled_manifest,
+        installed_manifest.as_ref(),
+    )?;
 
-        // Set executable permissions on the mo script
-        let mut perms = fs::metadata(&mole_binary)
-            .map_err(|e| format!("Failed to get metadata for mo: {}", e))?
-            .permissions();
-        perms.set_mode(0o755);
-        fs::set_permissions(&mole_binary, perms)
-            .map_err(|e| format!("Failed to set permissions on mo: {}", e))?;
-
-        // Set executable permissions on all bin scripts
-        let bin_dir = install_mole_dir.join("bin");
-        if bin_dir.exists() {
-            for entry in fs::read_dir(&bin_dir)
-                .map_err(|e| format!("Failed to read bin directory: {}", e))?
-            {
-                let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
-                let path = entry.path();
-                if path.is_file() {
-                    let mut perms = fs::metadata(&path)
-                        .map_err(|e| format!("Failed to get metadata: {}", e))?
-                        .permissions();
-                    perms.set_mode(0o755);
-                    fs::set_permissions(&path, perms)
-                        .map_err(|e| format!("Failed to set permissions: {}", e))?;
-                }
+    let mole_binary = install_mole_dir.join("mo");
+    set_executable(&mole_binary)?;
+
+    let bin_dir = install_mole_dir.join("bin");
+    if bin_dir.exists() {
+        for entry in fs::read_dir(&bin_dir)
+            .map_err(|e| format!("Failed to read bin directory: {}", e))?
+        {
+            let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+            let path = entry.path();
+            if path.is_file() {
+                set_executable(&path)?;
             }
         }
+    }
 
-        // Create symlink
+    #[cfg(unix)]
+    {
+        if install_path.exists() || install_path.symlink_metadata().is_ok() {
+            let _ = fs::remove_file(&install_path);
+        }
         std::os::unix::fs::symlink(&mole_binary, &install_path)
             .map_err(|e| format!("Failed to create symlink: {}", e))?;
     }
 
     #[cfg(not(unix))]
     {
-        // On non-Unix systems, just copy the file
         fs::copy(&mole_binary, &install_path)
             .map_err(|e| format!("Failed to copy mo binary: {}", e))?;
     }
 
-    Ok(install_path)
-}
-
-/// Recursively copy a directory
-fn copy_dir_recursive(src: &PathBuf, dst: &PathBuf) -> Result<(), String> {
-    fs::create_dir_all(dst)
-        .map_err(|e| format!("Failed to create directory {}: {}", dst.display(), e))?;
-
-    for entry in fs::read_dir(src)
-        .map_err(|e| format!("Failed to read directory {}: {}", src.display(), e))?
-    {
-        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
-        let path = entry.path();
-        let file_name = entry.file_name();
-        let dst_path = dst.join(&file_name);
+    write_manifest(&install_mole_dir, &bundled_manifest)?;
 
-        if path.is_dir() {
-            copy_dir_recursive(&path, &dst_path)?;
-        } else {
-            fs::copy(&path, &dst_path)
-                .map_err(|e| format!("Failed to copy file {} to {}: {}", path.display(), dst_path.display(), e))?;
-
-            // Preserve executable permissions on Unix
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                let src_perms = fs::metadata(&path)
-                    .map_err(|e| format!("Failed to get metadata: {}", e))?
-                    .permissions();
-                fs::set_permissions(&dst_path, src_perms)
-                    .map_err(|e| format!("Failed to set permissions: {}", e))?;
-            }
-        }
-    }
-
-    Ok(())
+    Ok(install_path)
 }
 
 /// Tauri command to ensure Mole is installed
@@ -149,3 +292,52 @@ pub fn get_home_dir() -> Result<String, String> {
         .ok_or_else(|| "Failed to get home directory".to_string())?;
     Ok(home.to_string_lossy().to_string())
 }
+
+/// Run an installed Mole `subcommand` with elevated privileges, prompting
+/// the user for their system password. `subcommand` is matched exactly
+/// against the capability manifest's `allowed_mole_subcommands` scope before
+/// this runs, so it's never attacker-controlled shell text by the time it
+/// reaches here.
+#[cfg(target_os = "macos")]
+fn run_elevated(mole_path: &Path, subcommand: &str) -> Result<String, String> {
+    let script = format!(
+        "do shell script \"{} {}\" with administrator privileges",
+        mole_path.display(),
+        subcommand
+    );
+
+    let output = std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .map_err(|e| format!("Failed to launch privileged optimize: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Privileged optimize failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn run_elevated(_mole_path: &Path, _subcommand: &str) -> Result<String, String> {
+    Err("Privileged optimize is only supported on macOS".to_string())
+}
+
+/// Tauri command to run a privileged Mole optimize subcommand (e.g. a
+/// deep-clean pass that needs to touch system-owned paths), gated by the
+/// `mole.privileged_optimize` capability
+#[tauri::command]
+pub fn run_privileged_optimize(app: AppHandle, subcommand: String) -> Result<String, String> {
+    crate::capabilities::authorize(
+        &app,
+        "run_privileged_optimize",
+        crate::capabilities::AuthContext::MoleSubcommand(&subcommand),
+    )?;
+
+    let mole_path = ensure_mole_installed(&app)?;
+    run_elevated(&mole_path, &subcommand)
+}
@@ -1,23 +1,178 @@
 use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::mpsc;
 use tokio::time::interval;
 
+/// Name of the active status history file
+const STATUS_HISTORY_FILE_NAME: &str = "status-history.jsonl";
+
+/// Default rotation threshold for the status history file, in bytes
+const DEFAULT_STATUS_HISTORY_MAX_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Default number of rotated backups to retain
+const DEFAULT_STATUS_HISTORY_MAX_BACKUPS: u32 = 5;
+
+/// Load status history rotation settings from persisted settings
+fn load_status_history_settings() -> (u64, u32) {
+    if let Ok(settings) = crate::settings::load_settings() {
+        let max_bytes = crate::settings::get_u64(
+            &settings,
+            "status_history_max_bytes",
+            DEFAULT_STATUS_HISTORY_MAX_BYTES,
+        );
+
+        let max_backups = settings
+            .get("status_history_max_backups")
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_STATUS_HISTORY_MAX_BACKUPS);
+
+        (max_bytes, max_backups)
+    } else {
+        (DEFAULT_STATUS_HISTORY_MAX_BYTES, DEFAULT_STATUS_HISTORY_MAX_BACKUPS)
+    }
+}
+
+/// One entry in the status history log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StatusHistoryEntry {
+    ts: String,
+    health: String,
+    status: MonitoringStatus,
+}
+
+/// Path to the active status history file
+fn status_history_path() -> Result<PathBuf, String> {
+    Ok(crate::paths::data_dir()?.join(STATUS_HISTORY_FILE_NAME))
+}
+
+/// Path to a numbered status history backup, e.g. `status-history.jsonl.1`
+fn status_history_backup_path(index: u32) -> Result<PathBuf, String> {
+    Ok(crate::paths::data_dir()?.join(format!("{}.{}", STATUS_HISTORY_FILE_NAME, index)))
+}
+
+/// Rotate the status history file if it has exceeded `max_bytes`, keeping at
+/// most `max_backups` numbered archives and discarding the oldest
+fn rotate_status_history_if_needed(max_bytes: u64, max_backups: u32) -> Result<(), String> {
+    let active_path = status_history_path()?;
+
+    let size = match fs::metadata(&active_path) {
+        Ok(metadata) => metadata.len(),
+        Err(_) => return Ok(()),
+    };
+
+    if size <= max_bytes || max_backups == 0 {
+        return Ok(());
+    }
+
+    // Delete the oldest backup if we're at capacity, then shift the rest up
+    let oldest = status_history_backup_path(max_backups)?;
+    if oldest.exists() {
+        fs::remove_file(&oldest)
+            .map_err(|e| format!("Failed to remove oldest status history backup: {}", e))?;
+    }
+
+    for index in (1..max_backups).rev() {
+        let from = status_history_backup_path(index)?;
+        if from.exists() {
+            let to = status_history_backup_path(index + 1)?;
+            fs::rename(&from, &to)
+                .map_err(|e| format!("Failed to rotate status history backup: {}", e))?;
+        }
+    }
+
+    fs::rename(&active_path, status_history_backup_path(1)?)
+        .map_err(|e| format!("Failed to rotate status history file: {}", e))?;
+
+    Ok(())
+}
+
+/// Append a completed status check to the rotating status history log
+fn append_status_history(entry: &StatusHistoryEntry) -> Result<(), String> {
+    let (max_bytes, max_backups) = load_status_history_settings();
+    rotate_status_history_if_needed(max_bytes, max_backups)?;
+
+    let active_path = status_history_path()?;
+    if let Some(parent) = active_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create status history directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string(entry)
+        .map_err(|e| format!("Failed to serialize status history entry: {}", e))?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&active_path)
+        .map_err(|e| format!("Failed to open status history file: {}", e))?;
+
+    writeln!(file, "{}", json)
+        .map_err(|e| format!("Failed to write status history entry: {}", e))?;
+
+    Ok(())
+}
+
+/// Parse up to `limit` entries from the tail of a status history file,
+/// newest last, skipping blank lines
+fn parse_tail_entries(path: &PathBuf, limit: usize) -> Vec<StatusHistoryEntry> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut entries: Vec<StatusHistoryEntry> = contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+
+    if entries.len() > limit {
+        entries = entries.split_off(entries.len() - limit);
+    }
+
+    entries
+}
+
+/// Read the most recent `limit` status history entries, reading back across
+/// rotated archives when the active file doesn't hold enough on its own
+fn read_status_history(limit: usize) -> Result<Vec<StatusHistoryEntry>, String> {
+    let mut collected: Vec<StatusHistoryEntry> = parse_tail_entries(&status_history_path()?, limit);
+
+    let mut backup_index = 1;
+    while collected.len() < limit {
+        let backup_path = status_history_backup_path(backup_index)?;
+        if !backup_path.exists() {
+            break;
+        }
+
+        let remaining = limit - collected.len();
+        let mut older = parse_tail_entries(&backup_path, remaining);
+        older.append(&mut collected);
+        collected = older;
+
+        backup_index += 1;
+    }
+
+    Ok(collected)
+}
+
+/// Tauri command to fetch the most recent status history entries
+#[tauri::command]
+pub fn get_status_history(limit: usize) -> Result<Vec<StatusHistoryEntry>, String> {
+    read_status_history(limit)
+}
+
 /// Load monitoring settings from persisted settings
 fn load_monitoring_settings() -> (bool, u64) {
     // Try to load from settings file
     if let Ok(settings) = crate::settings::load_settings() {
-        let enabled = settings
-            .get("monitoring_enabled")
-            .and_then(|v| v.parse::<bool>().ok())
-            .unwrap_or(true);
-
-        let interval_minutes = settings
-            .get("monitoring_interval_minutes")
-            .and_then(|v| v.parse::<u64>().ok())
-            .unwrap_or(30);
+        let enabled = crate::settings::get_bool(&settings, "monitoring_enabled", true);
+        let interval_minutes = crate::settings::get_u64(&settings, "monitoring_interval_minutes", 30);
 
         (enabled, interval_minutes)
     } else {
@@ -26,6 +181,92 @@ fn load_monitoring_settings() -> (bool, u64) {
     }
 }
 
+/// Default workspace paths excluded from filesystem-watch triggers
+const DEFAULT_WATCH_IGNORE: &str = ".git,node_modules,target,dist,build";
+
+/// Load filesystem-watch settings from persisted settings
+fn load_watch_settings() -> (bool, Vec<String>) {
+    if let Ok(settings) = crate::settings::load_settings() {
+        let enabled = crate::settings::get_bool(&settings, "monitoring_watch_enabled", false);
+
+        let ignore_list = crate::settings::get_string(&settings, "monitoring_watch_ignore", DEFAULT_WATCH_IGNORE)
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        (enabled, ignore_list)
+    } else {
+        (
+            false,
+            DEFAULT_WATCH_IGNORE
+                .split(',')
+                .map(|s| s.to_string())
+                .collect(),
+        )
+    }
+}
+
+/// Whether a filesystem event touches an ignored path component (e.g. `.git`)
+fn event_is_ignored(event: &notify::Event, ignore_list: &[String]) -> bool {
+    event.paths.iter().any(|path| {
+        path.components()
+            .any(|component| ignore_list.iter().any(|ignored| component.as_os_str() == ignored.as_str()))
+    })
+}
+
+/// Watch the workspace directory for changes and forward a debounced trigger
+/// once events have been quiet for ~2 seconds. Runs on a dedicated OS thread
+/// since `notify`'s watcher callback is synchronous.
+fn spawn_workspace_watcher(
+    workspace_path: std::path::PathBuf,
+    ignore_list: Vec<String>,
+    trigger_tx: mpsc::UnboundedSender<()>,
+) {
+    std::thread::spawn(move || {
+        use notify::Watcher;
+
+        let (raw_tx, raw_rx) = std::sync::mpsc::channel();
+        let mut watcher = match notify::recommended_watcher(raw_tx) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                log::error!("Failed to create workspace watcher: {}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = watcher.watch(&workspace_path, notify::RecursiveMode::Recursive) {
+            log::error!("Failed to watch workspace directory: {}", e);
+            return;
+        }
+
+        let debounce = Duration::from_secs(2);
+        let mut pending = false;
+
+        loop {
+            match raw_rx.recv_timeout(debounce) {
+                Ok(Ok(event)) => {
+                    if !event_is_ignored(&event, &ignore_list) {
+                        pending = true;
+                    }
+                }
+                Ok(Err(e)) => {
+                    log::warn!("Workspace watch error: {}", e);
+                }
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                    if pending {
+                        pending = false;
+                        if trigger_tx.send(()).is_err() {
+                            break;
+                        }
+                    }
+                }
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonitoringStatus {
     pub health: String,
@@ -38,6 +279,8 @@ pub struct MonitoringState {
     pub enabled: bool,
     pub interval_minutes: u64,
     pub last_status: Option<MonitoringStatus>,
+    #[serde(default)]
+    pub consecutive_failures: u32,
     #[serde(skip)]
     pub config_tx: Option<mpsc::UnboundedSender<u64>>,
 }
@@ -48,6 +291,7 @@ impl Default for MonitoringState {
             enabled: true,
             interval_minutes: 30,
             last_status: None,
+            consecutive_failures: 0,
             config_tx: None,
         }
     }
@@ -78,6 +322,20 @@ pub fn start_monitoring(app: AppHandle) {
 
     let app_clone = app.clone();
 
+    // Create a channel for debounced filesystem-watch triggers
+    let (watch_tx, mut watch_rx) = mpsc::unbounded_channel::<()>();
+
+    let (watch_enabled, watch_ignore) = load_watch_settings();
+    if watch_enabled {
+        // Ensure the workspace directory actually exists before watching it;
+        // on a first run it may not have been created yet, which would make
+        // `watcher.watch` fail and the watch thread exit silently
+        match crate::workspace::ensure_workspace(&app) {
+            Ok(workspace_path) => spawn_workspace_watcher(workspace_path, watch_ignore, watch_tx),
+            Err(e) => log::error!("Failed to set up workspace for watcher: {}", e),
+        }
+    }
+
     tauri::async_runtime::spawn(async move {
         let mut current_interval = initial_interval;
         let mut ticker = interval(Duration::from_secs(current_interval * 60));
@@ -106,6 +364,21 @@ pub fn start_monitoring(app: AppHandle) {
                         }
                     }
                 }
+                // Handle debounced workspace-change triggers
+                Some(()) = watch_rx.recv() => {
+                    let should_run = {
+                        let state_guard = state.lock().unwrap();
+                        state_guard.enabled
+                    };
+
+                    if should_run {
+                        log::info!("Workspace change detected, running status check");
+                        if let Err(e) = run_status_check(&app_clone, &state).await {
+                            log::error!("Failed to run status check: {}", e);
+                            let _ = app_clone.emit("monitoring:error", e);
+                        }
+                    }
+                }
             }
         }
     });
@@ -113,11 +386,50 @@ pub fn start_monitoring(app: AppHandle) {
     log::info!("Background monitoring started");
 }
 
-/// Run a single status check
-async fn run_status_check(
-    app: &AppHandle,
-    state: &Arc<Mutex<MonitoringState>>,
-) -> Result<(), String> {
+/// Retry policy for a failing status check
+#[derive(Debug, Clone, Copy)]
+struct RetryPolicy {
+    max_retries: u32,
+    base_delay_ms: u64,
+    max_delay_ms: u64,
+    failure_threshold: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 30_000,
+            failure_threshold: 3,
+        }
+    }
+}
+
+/// Load the retry/degraded-health policy from persisted settings
+fn load_retry_policy() -> RetryPolicy {
+    let defaults = RetryPolicy::default();
+
+    if let Ok(settings) = crate::settings::load_settings() {
+        RetryPolicy {
+            max_retries: settings
+                .get("monitoring_max_retries")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_retries),
+            base_delay_ms: crate::settings::get_u64(&settings, "monitoring_retry_base_ms", defaults.base_delay_ms),
+            max_delay_ms: crate::settings::get_u64(&settings, "monitoring_retry_max_ms", defaults.max_delay_ms),
+            failure_threshold: settings
+                .get("monitoring_degraded_threshold")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.failure_threshold),
+        }
+    } else {
+        defaults
+    }
+}
+
+/// Run `mo status --json` once and parse the result
+async fn check_mo_status(app: &AppHandle) -> Result<MonitoringStatus, String> {
     let mole_path = crate::mole::ensure_mole_installed(app)?;
     let workspace_path = crate::workspace::ensure_workspace(app)?;
 
@@ -146,22 +458,108 @@ async fn run_status_check(
         .unwrap_or("unknown")
         .to_string();
 
-    let monitoring_status = MonitoringStatus {
-        health: health.clone(),
+    Ok(MonitoringStatus {
+        health,
         last_update: chrono::Utc::now().to_rfc3339(),
-        status_json: status_json.clone(),
+        status_json,
+    })
+}
+
+/// Compute the backoff delay for a given retry attempt: the exponential delay
+/// is capped at `max_delay_ms`, then up to a quarter of it is added as jitter
+fn backoff_delay(policy: &RetryPolicy, attempt: u32) -> Duration {
+    let exp_delay = policy.base_delay_ms.saturating_mul(1u64 << attempt.min(20));
+    let capped = exp_delay.min(policy.max_delay_ms);
+    let jitter = rand::random::<u64>() % (capped / 4 + 1);
+    Duration::from_millis(capped + jitter)
+}
+
+/// Run a single status check, retrying on failure with exponential backoff
+/// before giving up. Returns an error (which the monitoring loop reports as
+/// `monitoring:error`) only after retries are exhausted, and emits
+/// `monitoring:health` with a synthetic "degraded" status once consecutive
+/// failures cross the configured threshold.
+async fn run_status_check(
+    app: &AppHandle,
+    state: &Arc<Mutex<MonitoringState>>,
+) -> Result<(), String> {
+    let policy = load_retry_policy();
+
+    let mut last_err = String::new();
+    let mut attempt = 0;
+    let monitoring_status = loop {
+        match check_mo_status(app).await {
+            Ok(status) => break Some(status),
+            Err(e) => {
+                last_err = e;
+                if attempt >= policy.max_retries {
+                    break None;
+                }
+                let delay = backoff_delay(&policy, attempt);
+                log::warn!(
+                    "Status check attempt {} failed, retrying in {:?}: {}",
+                    attempt + 1,
+                    delay,
+                    last_err
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+        }
     };
 
-    // Update state
-    {
+    let monitoring_status = match monitoring_status {
+        Some(status) => status,
+        None => {
+            let consecutive_failures = {
+                let mut state_guard = state.lock().unwrap();
+                state_guard.consecutive_failures += 1;
+                state_guard.consecutive_failures
+            };
+
+            if consecutive_failures >= policy.failure_threshold {
+                let degraded_status = MonitoringStatus {
+                    health: "degraded".to_string(),
+                    last_update: chrono::Utc::now().to_rfc3339(),
+                    status_json: serde_json::json!({ "consecutive_failures": consecutive_failures }),
+                };
+                let _ = app.emit("monitoring:health", degraded_status);
+            }
+
+            return Err(last_err);
+        }
+    };
+
+    // Reset the failure streak and, if we had been reported degraded, let the
+    // frontend know we've recovered
+    let recovered = {
         let mut state_guard = state.lock().unwrap();
+        let was_failing = state_guard.consecutive_failures >= policy.failure_threshold;
+        state_guard.consecutive_failures = 0;
         state_guard.last_status = Some(monitoring_status.clone());
+        was_failing
+    };
+
+    if recovered {
+        let _ = app.emit("monitoring:health", monitoring_status.clone());
     }
 
-    // Emit event to frontend
-    let _ = app.emit("monitoring:status", monitoring_status);
+    // Emit event to frontend, including the `://` channel so windows can
+    // subscribe to live updates instead of polling `get_cached_status`
+    let _ = app.emit("monitoring:status", monitoring_status.clone());
+    let _ = app.emit("monitoring://status", monitoring_status.clone());
+
+    // Persist to the rotating status history log so trends survive restarts
+    let history_entry = StatusHistoryEntry {
+        ts: monitoring_status.last_update.clone(),
+        health: monitoring_status.health.clone(),
+        status: monitoring_status.clone(),
+    };
+    if let Err(e) = append_status_history(&history_entry) {
+        log::warn!("Failed to append status history: {}", e);
+    }
 
-    log::info!("Status check completed, health: {}", health);
+    log::info!("Status check completed, health: {}", monitoring_status.health);
 
     Ok(())
 }
@@ -0,0 +1,102 @@
+use directories::ProjectDirs;
+use std::path::PathBuf;
+use std::sync::Once;
+
+/// Resolve the platform-specific project directories for Valet.
+///
+/// On macOS this resolves to `~/Library/Application Support/<bundle id>`,
+/// on Linux to `$XDG_DATA_HOME/<app>` (falling back to `~/.local/share/<app>`),
+/// and on Windows to `%APPDATA%\<organization>\<application>\data`.
+fn project_dirs() -> Result<ProjectDirs, String> {
+    ProjectDirs::from("com", "Valet", "Valet")
+        .ok_or_else(|| "Failed to determine application data directory".to_string())
+}
+
+/// Directory used by installs that predate this cross-platform path
+/// resolution, when macOS paths were hardcoded as
+/// `~/Library/Application Support/Valet` rather than nested under the
+/// `com.Valet.Valet` bundle id that `ProjectDirs` resolves to
+#[cfg(target_os = "macos")]
+fn legacy_macos_data_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join("Library/Application Support/Valet"))
+}
+
+/// Move a pre-existing install from the legacy macOS directory into the new
+/// `ProjectDirs`-resolved one, the first time the new directory is needed.
+/// Without this, every existing macOS install's settings, audit log, status
+/// history, and installed Mole tree would be silently orphaned under the old
+/// path the very first time this version of the app runs.
+#[cfg(target_os = "macos")]
+fn migrate_legacy_macos_dir(new_dir: &PathBuf) {
+    static MIGRATED: Once = Once::new();
+
+    MIGRATED.call_once(|| {
+        if new_dir.exists() {
+            return;
+        }
+
+        let Some(legacy_dir) = legacy_macos_data_dir() else {
+            return;
+        };
+
+        if !legacy_dir.exists() {
+            return;
+        }
+
+        if let Some(parent) = new_dir.parent() {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                log::warn!("Failed to prepare {} for legacy data migration: {}", parent.display(), e);
+                return;
+            }
+        }
+
+        match std::fs::rename(&legacy_dir, new_dir) {
+            Ok(()) => log::info!(
+                "Migrated legacy data directory {} to {}",
+                legacy_dir.display(),
+                new_dir.display()
+            ),
+            Err(e) => log::warn!(
+                "Failed to migrate legacy data directory {} to {}: {}",
+                legacy_dir.display(),
+                new_dir.display(),
+                e
+            ),
+        }
+    });
+}
+
+/// Root data directory for the app, e.g. `~/Library/Application Support/com.Valet.Valet`
+pub fn data_dir() -> Result<PathBuf, String> {
+    let dir = project_dirs()?.data_dir().to_path_buf();
+
+    #[cfg(target_os = "macos")]
+    migrate_legacy_macos_dir(&dir);
+
+    Ok(dir)
+}
+
+/// Directory where the `mo` symlink (or copy, on non-Unix) is installed
+pub fn bin_dir() -> Result<PathBuf, String> {
+    Ok(data_dir()?.join("bin"))
+}
+
+/// Directory where the bundled Mole tree is installed
+pub fn mole_dir() -> Result<PathBuf, String> {
+    Ok(data_dir()?.join("mole"))
+}
+
+/// Path to the settings file
+pub fn settings_path() -> Result<PathBuf, String> {
+    Ok(data_dir()?.join("settings.json"))
+}
+
+/// Path to the audit log file
+pub fn audit_log_path() -> Result<PathBuf, String> {
+    Ok(data_dir()?.join("audit.log"))
+}
+
+/// Root directory for the AI workspace
+pub fn workspace_dir() -> Result<PathBuf, String> {
+    Ok(data_dir()?.join("workspace"))
+}
@@ -1,6 +1,47 @@
 use serde::{Deserialize, Serialize};
 use std::process::Command;
 
+#[cfg(target_os = "macos")]
+mod macos {
+    use objc2_av_foundation::{AVAuthorizationStatus, AVCaptureDevice, AVMediaTypeAudio};
+
+    /// Check the cached authorization status without prompting the user
+    pub fn microphone_authorized() -> bool {
+        let status = unsafe { AVCaptureDevice::authorizationStatusForMediaType(AVMediaTypeAudio) };
+        status == AVAuthorizationStatus::Authorized
+    }
+
+    /// Trigger the system microphone-access prompt if the user hasn't
+    /// decided yet; a no-op if already authorized or denied
+    pub fn request_microphone_access() {
+        unsafe {
+            let handler = block2::RcBlock::new(|granted: bool| {
+                log::info!("Microphone access request completed, granted: {}", granted);
+            });
+            AVCaptureDevice::requestAccessForMediaType_completionHandler(AVMediaTypeAudio, &handler);
+        }
+    }
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrustedWithOptions(options: core_foundation::dictionary::CFDictionaryRef) -> bool;
+    }
+
+    /// Check accessibility trust via `AXIsProcessTrustedWithOptions`, without
+    /// prompting the user to grant it
+    pub fn accessibility_trusted() -> bool {
+        use core_foundation::base::TCFType;
+        use core_foundation::boolean::CFBoolean;
+        use core_foundation::dictionary::CFDictionary;
+        use core_foundation::string::CFString;
+
+        let prompt_key = CFString::from_static_string("AXTrustedCheckOptionPrompt");
+        let options = CFDictionary::from_CFType_pairs(&[(prompt_key.as_CFType(), CFBoolean::false_value().as_CFType())]);
+
+        unsafe { AXIsProcessTrustedWithOptions(options.as_concrete_TypeRef()) }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PermissionStatus {
     pub microphone: bool,
@@ -19,12 +60,15 @@ pub fn check_permissions_command() -> Result<PermissionStatus, String> {
     })
 }
 
-/// Request microphone permission (will trigger system prompt)
+/// Request microphone permission, triggering the system prompt if the user
+/// hasn't decided yet
 #[tauri::command]
 pub fn request_microphone_permission_command() -> Result<(), String> {
-    // On macOS, microphone permission is requested automatically when the app tries to access the microphone
-    // The frontend will handle this through the browser's getUserMedia API
-    // This command is a no-op on the Rust side
+    #[cfg(target_os = "macos")]
+    {
+        macos::request_microphone_access();
+    }
+
     Ok(())
 }
 
@@ -53,20 +97,11 @@ pub fn open_system_preferences_command(pane: String) -> Result<(), String> {
 fn check_microphone_permission() -> bool {
     #[cfg(target_os = "macos")]
     {
-        // On macOS 10.14+, we need to check AVCaptureDevice authorization status
-        // For now, we'll use a heuristic approach via tccutil
-        let output = Command::new("sqlite3")
-            .arg(format!("{}/Library/Application Support/com.apple.TCC/TCC.db", std::env::var("HOME").unwrap_or_default()))
-            .arg("SELECT allowed FROM access WHERE service='kTCCServiceMicrophone' AND client='com.valet.mac';")
-            .output();
-
-        if let Ok(output) = output {
-            let result = String::from_utf8_lossy(&output.stdout);
-            return result.trim() == "1";
-        }
+        return macos::microphone_authorized();
     }
 
     // Default to false if we can't check
+    #[cfg(not(target_os = "macos"))]
     false
 }
 
@@ -97,19 +132,10 @@ fn check_full_disk_access() -> bool {
 fn check_accessibility_permission() -> bool {
     #[cfg(target_os = "macos")]
     {
-        // Use AXIsProcessTrusted API to check accessibility permission
-        // For now, we'll use a simpler heuristic
-        let output = Command::new("sqlite3")
-            .arg(format!("{}/Library/Application Support/com.apple.TCC/TCC.db", std::env::var("HOME").unwrap_or_default()))
-            .arg("SELECT allowed FROM access WHERE service='kTCCServiceAccessibility' AND client='com.valet.mac';")
-            .output();
-
-        if let Ok(output) = output {
-            let result = String::from_utf8_lossy(&output.stdout);
-            return result.trim() == "1";
-        }
+        return macos::accessibility_trusted();
     }
 
     // Default to false if we can't check
+    #[cfg(not(target_os = "macos"))]
     false
 }
@@ -1,4 +1,3 @@
-use serde_json::Value;
 use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::{Read, Write};
@@ -8,61 +7,143 @@ use std::sync::Mutex;
 /// Lazy static settings store
 static SETTINGS: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
 
-/// Get the path to the settings file
-pub fn get_settings_path() -> Result<PathBuf, String> {
-    let home_dir = dirs::home_dir()
-        .ok_or_else(|| "Failed to get home directory".to_string())?;
+/// Reserved key under which the settings schema version is stored
+const SCHEMA_VERSION_KEY: &str = "__schema_version";
 
-    let valet_dir = home_dir.join("Library/Application Support/Valet");
+/// Current settings schema version. Bump this and append a migration to
+/// `MIGRATIONS` whenever the on-disk shape changes.
+const CURRENT_SCHEMA_VERSION: u32 = 1;
 
-    // Ensure the Valet directory exists
-    fs::create_dir_all(&valet_dir)
-        .map_err(|e| format!("Failed to create Valet directory: {}", e))?;
+/// A migration takes the settings map from one schema version to the next.
+/// `MIGRATIONS[i]` migrates from version `i` to version `i + 1`.
+type Migration = fn(&mut HashMap<String, String>);
 
-    Ok(valet_dir.join("settings.json"))
-}
+const MIGRATIONS: &[Migration] = &[migrate_v0_to_v1];
 
-/// Load settings from disk
-pub fn load_settings() -> Result<HashMap<String, String>, String> {
-    let settings_path = get_settings_path()?;
+/// v0 -> v1: rename `monitoring_interval` to `monitoring_interval_minutes`
+fn migrate_v0_to_v1(settings: &mut HashMap<String, String>) {
+    if let Some(value) = settings.remove("monitoring_interval") {
+        settings
+            .entry("monitoring_interval_minutes".to_string())
+            .or_insert(value);
+    }
+}
 
-    if !settings_path.exists() {
-        return Ok(HashMap::new());
+/// Apply any pending migrations in order, bumping the stored schema version
+/// as each one runs. Returns `true` if the map was modified.
+fn migrate_settings(settings: &mut HashMap<String, String>) -> bool {
+    let stored_version: u32 = settings
+        .get(SCHEMA_VERSION_KEY)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+
+    let mut version = stored_version;
+    while (version as usize) < MIGRATIONS.len() {
+        MIGRATIONS[version as usize](settings);
+        version += 1;
     }
 
-    let mut file = File::open(&settings_path)
-        .map_err(|e| format!("Failed to open settings file: {}", e))?;
+    if version != stored_version || !settings.contains_key(SCHEMA_VERSION_KEY) {
+        settings.insert(SCHEMA_VERSION_KEY.to_string(), CURRENT_SCHEMA_VERSION.to_string());
+        true
+    } else {
+        false
+    }
+}
 
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)
-        .map_err(|e| format!("Failed to read settings file: {}", e))?;
+/// Get the path to the settings file
+pub fn get_settings_path() -> Result<PathBuf, String> {
+    let settings_path = crate::paths::settings_path()?;
 
-    if contents.trim().is_empty() {
-        return Ok(HashMap::new());
+    if let Some(parent) = settings_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings directory: {}", e))?;
     }
 
-    let settings: HashMap<String, String> = serde_json::from_str(&contents)
-        .map_err(|e| format!("Failed to parse settings file: {}", e))?;
+    Ok(settings_path)
+}
+
+/// Load settings from disk, applying any pending schema migrations
+pub fn load_settings() -> Result<HashMap<String, String>, String> {
+    let settings_path = get_settings_path()?;
+
+    let mut settings = if !settings_path.exists() {
+        HashMap::new()
+    } else {
+        let mut file = File::open(&settings_path)
+            .map_err(|e| format!("Failed to open settings file: {}", e))?;
+
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)
+            .map_err(|e| format!("Failed to read settings file: {}", e))?;
+
+        if contents.trim().is_empty() {
+            HashMap::new()
+        } else {
+            serde_json::from_str(&contents)
+                .map_err(|e| format!("Failed to parse settings file: {}", e))?
+        }
+    };
+
+    if migrate_settings(&mut settings) {
+        save_settings(&settings)?;
+    }
 
     Ok(settings)
 }
 
-/// Save settings to disk
+/// Save settings to disk. Writes to a temp file in the same directory and
+/// atomically renames it over `settings.json` so a crash mid-write can never
+/// leave a truncated or corrupt file behind.
 fn save_settings(settings: &HashMap<String, String>) -> Result<(), String> {
     let settings_path = get_settings_path()?;
 
     let json = serde_json::to_string_pretty(settings)
         .map_err(|e| format!("Failed to serialize settings: {}", e))?;
 
-    let mut file = File::create(&settings_path)
-        .map_err(|e| format!("Failed to create settings file: {}", e))?;
+    let tmp_path = settings_path.with_extension("json.tmp");
+
+    {
+        let mut file = File::create(&tmp_path)
+            .map_err(|e| format!("Failed to create temp settings file: {}", e))?;
+
+        file.write_all(json.as_bytes())
+            .map_err(|e| format!("Failed to write temp settings file: {}", e))?;
+
+        file.sync_all()
+            .map_err(|e| format!("Failed to flush temp settings file: {}", e))?;
+    }
 
-    file.write_all(json.as_bytes())
-        .map_err(|e| format!("Failed to write settings file: {}", e))?;
+    fs::rename(&tmp_path, &settings_path)
+        .map_err(|e| format!("Failed to replace settings file: {}", e))?;
 
     Ok(())
 }
 
+/// Get a boolean setting, falling back to `default` if missing or unparsable
+pub fn get_bool(settings: &HashMap<String, String>, key: &str, default: bool) -> bool {
+    settings
+        .get(key)
+        .and_then(|v| v.parse::<bool>().ok())
+        .unwrap_or(default)
+}
+
+/// Get a `u64` setting, falling back to `default` if missing or unparsable
+pub fn get_u64(settings: &HashMap<String, String>, key: &str, default: u64) -> u64 {
+    settings
+        .get(key)
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(default)
+}
+
+/// Get a string setting, falling back to `default` if missing
+pub fn get_string(settings: &HashMap<String, String>, key: &str, default: &str) -> String {
+    settings
+        .get(key)
+        .cloned()
+        .unwrap_or_else(|| default.to_string())
+}
+
 /// Get a setting value
 #[tauri::command]
 pub fn get_setting_command(key: String) -> Result<Option<String>, String> {
@@ -0,0 +1,85 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+/// Default hotkey that summons the menubar window: Option+Command+V
+const DEFAULT_SHORTCUT: &str = "Alt+Super+KeyV";
+
+/// Currently-registered shortcut, tracked so it can be unregistered before a
+/// replacement is registered
+static ACTIVE_SHORTCUT: Mutex<Option<Shortcut>> = Mutex::new(None);
+
+/// Load the configured global shortcut, falling back to the default
+fn load_shortcut_setting() -> String {
+    crate::settings::load_settings()
+        .map(|settings| crate::settings::get_string(&settings, "global_shortcut", DEFAULT_SHORTCUT))
+        .unwrap_or_else(|_| DEFAULT_SHORTCUT.to_string())
+}
+
+/// Toggle the `main` window's visibility exactly like the tray click handler,
+/// and kick off a background status refresh
+pub fn summon_and_refresh(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        if window.is_visible().unwrap_or(false) {
+            let _ = window.hide();
+        } else {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = crate::monitoring::trigger_status_check(app).await {
+            log::warn!("Status refresh triggered by global shortcut failed: {}", e);
+        }
+    });
+}
+
+/// Register `shortcut_str` as the active global shortcut, unregistering
+/// whatever was previously registered first
+pub fn register_shortcut(app: &AppHandle, shortcut_str: &str) -> Result<(), String> {
+    let shortcut: Shortcut = shortcut_str
+        .parse()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", shortcut_str, e))?;
+
+    let mut active = ACTIVE_SHORTCUT
+        .lock()
+        .map_err(|e| format!("Failed to lock active shortcut: {}", e))?;
+
+    let previous = active.take();
+    if let Some(previous) = previous {
+        let _ = app.global_shortcut().unregister(previous);
+    }
+
+    if let Err(e) = app.global_shortcut().register(shortcut) {
+        // Put the previous shortcut back so a failed change leaves it active
+        if let Some(previous) = previous {
+            if app.global_shortcut().register(previous).is_ok() {
+                *active = Some(previous);
+            }
+        }
+        return Err(format!(
+            "Shortcut '{}' could not be registered (it may already be in use by another app): {}",
+            shortcut_str, e
+        ));
+    }
+
+    *active = Some(shortcut);
+    Ok(())
+}
+
+/// Register the shortcut configured in settings (or the default) at startup
+pub fn register_configured_shortcut(app: &AppHandle) -> Result<(), String> {
+    register_shortcut(app, &load_shortcut_setting())
+}
+
+/// Change the global shortcut, persisting the new setting and re-registering
+/// it immediately. On failure (e.g. the combo is already taken) the
+/// previously-active shortcut is left registered.
+#[tauri::command]
+pub fn set_global_shortcut_command(app: AppHandle, shortcut: String) -> Result<(), String> {
+    register_shortcut(&app, &shortcut)?;
+    crate::settings::set_setting_command("global_shortcut".to_string(), shortcut)?;
+    Ok(())
+}
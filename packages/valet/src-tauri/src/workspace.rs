@@ -4,10 +4,7 @@ use tauri::{AppHandle, Manager};
 
 /// Get the workspace directory path
 pub fn get_workspace_path() -> Result<PathBuf, String> {
-    let home_dir = dirs::home_dir()
-        .ok_or_else(|| "Failed to get home directory".to_string())?;
-
-    Ok(home_dir.join("Library/Application Support/Valet/workspace"))
+    crate::paths::workspace_dir()
 }
 
 /// Get the path to bundled resources